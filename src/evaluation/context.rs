@@ -1,8 +1,30 @@
-use std::collections::HashMap;
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    sync::Arc,
+};
 
+use serde::{
+    de, ser::SerializeMap, Deserialize, Deserializer, Serialize, Serializer,
+};
+use time::OffsetDateTime;
 use typed_builder::TypedBuilder;
 
-use crate::EvaluationContextFieldValue;
+use crate::{EvaluationContextFieldValue, StructMap};
+
+/// How [`EvaluationContext::merge_with`] resolves keys present on both sides.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum MergeStrategy {
+    /// Keep the value already set on `self`; `other` only fills in missing keys.
+    KeepExisting,
+
+    /// Let `other` overwrite any value already set on `self`.
+    Overwrite,
+
+    /// Let `other` overwrite scalar values, but when both sides hold a
+    /// [`Struct`](EvaluationContextFieldValue::Struct) under the same key, recursively merge the
+    /// underlying maps rather than replacing wholesale.
+    DeepMergeStructs,
+}
 
 /// The evaluation context provides ambient information for the purposes of flag evaluation.
 /// Contextual data may be used as the basis for targeting, including rule-based evaluation,
@@ -49,20 +71,184 @@ impl EvaluationContext {
         self.custom_fields.insert(key.into(), value.into());
     }
 
+    /// Return the boolean value stored under `key`, if any.
+    pub fn get_bool(&self, key: &str) -> Option<bool> {
+        match self.custom_fields.get(key)? {
+            EvaluationContextFieldValue::Bool(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Return the integer value stored under `key`, if any.
+    pub fn get_int(&self, key: &str) -> Option<i64> {
+        match self.custom_fields.get(key)? {
+            EvaluationContextFieldValue::Int(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Return the floating point value stored under `key`, if any.
+    ///
+    /// An [`Int`](EvaluationContextFieldValue::Int) field is coerced to a float so callers do not
+    /// have to special-case authors writing a whole number where a float is expected.
+    pub fn get_float(&self, key: &str) -> Option<f64> {
+        match self.custom_fields.get(key)? {
+            EvaluationContextFieldValue::Float(value) => Some(*value),
+            EvaluationContextFieldValue::Int(value) => Some(*value as f64),
+            _ => None,
+        }
+    }
+
+    /// Return the string value stored under `key`, if any.
+    pub fn get_string(&self, key: &str) -> Option<&str> {
+        match self.custom_fields.get(key)? {
+            EvaluationContextFieldValue::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Return the datetime value stored under `key`, if any.
+    pub fn get_datetime(&self, key: &str) -> Option<OffsetDateTime> {
+        match self.custom_fields.get(key)? {
+            EvaluationContextFieldValue::DateTime(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Return the structure stored under `key` downcast to `T`, if any.
+    pub fn get_struct<T: 'static>(&self, key: &str) -> Option<&T> {
+        self.custom_fields
+            .get(key)?
+            .as_struct()?
+            .as_any()
+            .downcast_ref::<T>()
+    }
+
+    /// Iterate over all custom field key/value pairs.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &EvaluationContextFieldValue)> {
+        self.custom_fields
+            .iter()
+            .map(|(key, value)| (key.as_str(), value))
+    }
+
     /// Merge `other` into `self` if corresponding field is not set.
     /// Meaning values set into `self` has higher precedence.
     pub fn merge_missing(&mut self, other: &Self) {
-        if self.targeting_key.is_none() {
-            if let Some(targeting_key) = &other.targeting_key {
-                self.targeting_key = Some(targeting_key.clone());
+        self.merge_with(other, MergeStrategy::KeepExisting);
+    }
+
+    /// Merge `other` into `self` using `strategy` to resolve keys present on both sides.
+    pub fn merge_with(&mut self, other: &Self, strategy: MergeStrategy) {
+        match strategy {
+            MergeStrategy::KeepExisting => {
+                if self.targeting_key.is_none() {
+                    self.targeting_key.clone_from(&other.targeting_key);
+                }
+            }
+            MergeStrategy::Overwrite | MergeStrategy::DeepMergeStructs => {
+                if other.targeting_key.is_some() {
+                    self.targeting_key.clone_from(&other.targeting_key);
+                }
             }
         }
 
-        other.custom_fields.iter().for_each(|(key, value)| {
-            if !self.custom_fields.contains_key(key) {
-                self.custom_fields.insert(key.clone(), value.clone());
+        for (key, value) in &other.custom_fields {
+            match self.custom_fields.entry(key.clone()) {
+                Entry::Vacant(entry) => {
+                    entry.insert(value.clone());
+                }
+                Entry::Occupied(mut entry) => match strategy {
+                    MergeStrategy::KeepExisting => {}
+                    MergeStrategy::Overwrite => {
+                        entry.insert(value.clone());
+                    }
+                    MergeStrategy::DeepMergeStructs => {
+                        let merged = deep_merge_value(entry.get(), value);
+                        entry.insert(merged);
+                    }
+                },
             }
-        });
+        }
+    }
+}
+
+/// Merge `incoming` into `existing`: when both are map-backed
+/// [`Struct`](EvaluationContextFieldValue::Struct)s recurse into their fields, otherwise `incoming`
+/// wins.
+fn deep_merge_value(
+    existing: &EvaluationContextFieldValue,
+    incoming: &EvaluationContextFieldValue,
+) -> EvaluationContextFieldValue {
+    if let (Some(existing), Some(incoming)) = (as_struct_map(existing), as_struct_map(incoming)) {
+        return EvaluationContextFieldValue::Struct(Arc::new(deep_merge_struct_maps(
+            existing, incoming,
+        )));
+    }
+
+    incoming.clone()
+}
+
+fn deep_merge_struct_maps(existing: &StructMap, incoming: &StructMap) -> StructMap {
+    let mut merged = existing.0.clone();
+    for (key, value) in &incoming.0 {
+        match merged.entry(key.clone()) {
+            Entry::Vacant(entry) => {
+                entry.insert(value.clone());
+            }
+            Entry::Occupied(mut entry) => {
+                let value = deep_merge_value(entry.get(), value);
+                entry.insert(value);
+            }
+        }
+    }
+    StructMap(merged)
+}
+
+fn as_struct_map(value: &EvaluationContextFieldValue) -> Option<&StructMap> {
+    value.as_struct()?.as_any().downcast_ref::<StructMap>()
+}
+
+/// The evaluation context serializes to a flat JSON object: the targeting key is emitted under the
+/// `targetingKey` key and every custom field is emitted alongside it. Deserialization reverses this,
+/// pulling `targetingKey` back out and collecting the remaining entries into `custom_fields`.
+impl Serialize for EvaluationContext {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut map = serializer.serialize_map(None)?;
+        if let Some(targeting_key) = &self.targeting_key {
+            map.serialize_entry("targetingKey", targeting_key)?;
+        }
+        for (key, value) in &self.custom_fields {
+            map.serialize_entry(key, value)?;
+        }
+        map.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for EvaluationContext {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let object = match serde_json::Value::deserialize(deserializer)? {
+            serde_json::Value::Object(object) => object,
+            _ => return Err(de::Error::custom("expected a JSON object")),
+        };
+
+        let mut context = EvaluationContext::default();
+        for (key, value) in object {
+            if key == "targetingKey" {
+                context.targeting_key = value.as_str().map(ToString::to_string);
+                continue;
+            }
+            context
+                .custom_fields
+                .insert(key, EvaluationContextFieldValue::from_json(value));
+        }
+
+        Ok(context)
     }
 }
 
@@ -71,7 +257,7 @@ mod tests {
     use std::sync::Arc;
 
     use spec::spec;
-    use time::OffsetDateTime;
+    use time::{format_description::well_known::Rfc3339, OffsetDateTime};
 
     use super::*;
 
@@ -143,7 +329,62 @@ mod tests {
         assert_eq!(context, other);
     }
 
-    #[derive(Clone, PartialEq, Eq, TypedBuilder, Debug)]
+    #[test]
+    fn merge_with_overwrite_prefers_other() {
+        let mut context = EvaluationContext::builder()
+            .targeting_key("Key")
+            .build()
+            .with_custom_field("Key", "Value");
+
+        context.merge_with(
+            &EvaluationContext::builder()
+                .targeting_key("Another Key")
+                .build()
+                .with_custom_field("Key", "Another Value"),
+            MergeStrategy::Overwrite,
+        );
+
+        assert_eq!(context.targeting_key, Some("Another Key".to_string()));
+        assert_eq!(context.get_string("Key"), Some("Another Value"));
+    }
+
+    #[test]
+    fn merge_with_deep_merges_structs() {
+        fn struct_field(
+            fields: &[(&str, EvaluationContextFieldValue)],
+        ) -> EvaluationContextFieldValue {
+            let map = fields
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.clone()))
+                .collect();
+            EvaluationContextFieldValue::Struct(Arc::new(StructMap(map)))
+        }
+
+        let mut context = EvaluationContext::default().with_custom_field(
+            "user",
+            struct_field(&[("id", EvaluationContextFieldValue::Int(1))]),
+        );
+
+        context.merge_with(
+            &EvaluationContext::default().with_custom_field(
+                "user",
+                struct_field(&[(
+                    "name",
+                    EvaluationContextFieldValue::String("Bob".to_string()),
+                )]),
+            ),
+            MergeStrategy::DeepMergeStructs,
+        );
+
+        let merged = as_struct_map(context.custom_fields.get("user").unwrap()).unwrap();
+        assert_eq!(merged.0.get("id"), Some(&EvaluationContextFieldValue::Int(1)));
+        assert_eq!(
+            merged.0.get("name"),
+            Some(&EvaluationContextFieldValue::String("Bob".to_string()))
+        );
+    }
+
+    #[derive(Clone, PartialEq, Eq, TypedBuilder, Debug, Serialize)]
     pub struct DummyStruct {
         pub id: i64,
 
@@ -203,15 +444,128 @@ mod tests {
             Some(&EvaluationContextFieldValue::DateTime(now_time))
         );
         assert_eq!(
-            *context
+            context
                 .custom_fields
                 .get("Struct")
                 .unwrap()
                 .as_struct()
                 .unwrap()
-                .downcast::<DummyStruct>()
+                .as_any()
+                .downcast_ref::<DummyStruct>()
                 .unwrap(),
-            struct_value
+            &struct_value
+        );
+    }
+
+    #[spec(
+        number = "3.1.3",
+        text = "The evaluation context MUST support fetching the custom fields by key and also fetching all key value pairs."
+    )]
+    #[test]
+    fn typed_field_access() {
+        let struct_value = DummyStruct::builder().id(200).name("Bob").build();
+
+        let context = EvaluationContext::default()
+            .with_custom_field("Bool", true)
+            .with_custom_field("Int", 100)
+            .with_custom_field("Float", 3.14)
+            .with_custom_field("String", "Hello")
+            .with_custom_field(
+                "Struct",
+                EvaluationContextFieldValue::Struct(Arc::new(struct_value.clone())),
+            );
+
+        assert_eq!(context.get_bool("Bool"), Some(true));
+        assert_eq!(context.get_int("Int"), Some(100));
+        assert_eq!(context.get_float("Float"), Some(3.14));
+        assert_eq!(context.get_string("String"), Some("Hello"));
+        assert_eq!(context.get_struct::<DummyStruct>("Struct"), Some(&struct_value));
+
+        // An integer field is coerced when read as a float.
+        assert_eq!(context.get_float("Int"), Some(100.0));
+
+        // Type mismatches and missing keys yield `None`.
+        assert_eq!(context.get_int("Bool"), None);
+        assert_eq!(context.get_string("Missing"), None);
+
+        assert_eq!(context.iter().count(), 5);
+    }
+
+    #[test]
+    fn serialize_emits_flat_targeting_key_and_fields() {
+        let context = EvaluationContext::builder()
+            .targeting_key("user-1")
+            .build()
+            .with_custom_field("email", "bob@acme.com");
+
+        let json: serde_json::Value =
+            serde_json::from_str(&serde_json::to_string(&context).unwrap()).unwrap();
+
+        assert_eq!(json["targetingKey"], "user-1");
+        assert_eq!(json["email"], "bob@acme.com");
+    }
+
+    #[test]
+    fn deserialize_coerces_numbers_and_parses_datetime() {
+        let now_time = OffsetDateTime::now_utc();
+        let json = serde_json::json!({
+            "targetingKey": "user-1",
+            "int": 100,
+            "float": 3.14,
+            "string": "Hello",
+            "datetime": now_time.format(&Rfc3339).unwrap(),
+            "struct": { "nested": { "id": 1 }, "name": "Bob" },
+        });
+
+        let context: EvaluationContext = serde_json::from_value(json).unwrap();
+
+        assert_eq!(context.targeting_key, Some("user-1".to_string()));
+        assert_eq!(context.get_int("int"), Some(100));
+        assert_eq!(context.get_float("float"), Some(3.14));
+        assert_eq!(context.get_string("string"), Some("Hello"));
+        assert_eq!(context.get_datetime("datetime"), Some(now_time));
+
+        // A nested object becomes a map-backed `Struct`, recursively.
+        let outer = as_struct_map(context.custom_fields.get("struct").unwrap()).unwrap();
+        assert_eq!(
+            outer.0.get("name"),
+            Some(&EvaluationContextFieldValue::String("Bob".to_string()))
+        );
+        let inner = as_struct_map(outer.0.get("nested").unwrap()).unwrap();
+        assert_eq!(inner.0.get("id"), Some(&EvaluationContextFieldValue::Int(1)));
+    }
+
+    #[test]
+    fn context_round_trips_through_json() {
+        let context = EvaluationContext::builder()
+            .targeting_key("user-1")
+            .build()
+            .with_custom_field("bool", true)
+            .with_custom_field("int", 7)
+            .with_custom_field("string", "Hello");
+
+        let round_tripped: EvaluationContext =
+            serde_json::from_str(&serde_json::to_string(&context).unwrap()).unwrap();
+
+        assert_eq!(round_tripped, context);
+    }
+
+    #[test]
+    fn resolution_details_round_trips_reason() {
+        use crate::{EvaluationReason, ResolutionDetails};
+
+        let mut details = ResolutionDetails::<bool>::new(true).with_metadata("ruleId", "r-1");
+        details.reason = Some(EvaluationReason::TargetingMatch);
+
+        let json = serde_json::to_string(&details).unwrap();
+        assert!(json.contains("\"TARGETING_MATCH\""));
+
+        let round_tripped: ResolutionDetails<bool> = serde_json::from_str(&json).unwrap();
+        assert!(round_tripped.value);
+        assert_eq!(round_tripped.reason, Some(EvaluationReason::TargetingMatch));
+        assert_eq!(
+            round_tripped.flag_metadata.unwrap().get_string("ruleId"),
+            Some("r-1")
         );
     }
 }
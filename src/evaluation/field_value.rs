@@ -0,0 +1,211 @@
+use std::{any::Any, collections::HashMap, fmt, sync::Arc};
+
+use serde::{
+    de, ser::SerializeMap, Deserialize, Deserializer, Serialize, Serializer,
+};
+use time::{format_description::well_known::Rfc3339, OffsetDateTime};
+
+/// A value held by a custom field of an [`EvaluationContext`](crate::EvaluationContext).
+///
+/// The specification allows custom field values of type boolean, string, number, datetime or
+/// structure. Structures are type-erased: any value that can be downcast at evaluation time and
+/// serialized to JSON for transport to a remote flag-resolution backend is accepted.
+#[derive(Clone)]
+pub enum EvaluationContextFieldValue {
+    /// A boolean value.
+    Bool(bool),
+
+    /// An integer value.
+    Int(i64),
+
+    /// A floating point value.
+    Float(f64),
+
+    /// A string value.
+    String(String),
+
+    /// A datetime value.
+    DateTime(OffsetDateTime),
+
+    /// An arbitrary, user-defined structure.
+    ///
+    /// The value is type-erased so that providers can downcast it back to the concrete type at
+    /// evaluation time, while still being able to serialize it to JSON for remote resolution.
+    Struct(Arc<dyn StructValue>),
+}
+
+/// A type that may be stored in [`EvaluationContextFieldValue::Struct`].
+///
+/// The blanket implementation covers any `'static` value that is both serializable and thread
+/// safe, so user structs deriving [`serde::Serialize`] satisfy it automatically while remaining
+/// downcastable via [`StructValue::as_any`].
+pub trait StructValue: erased_serde::Serialize + Any + Send + Sync {
+    /// Return `self` as [`Any`] so callers can downcast to the concrete type.
+    fn as_any(&self) -> &dyn Any;
+}
+
+impl<T> StructValue for T
+where
+    T: erased_serde::Serialize + Any + Send + Sync,
+{
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+erased_serde::serialize_trait_object!(StructValue);
+
+/// A generic, map-backed [`Struct`](EvaluationContextFieldValue::Struct) value.
+///
+/// Used when a structure is materialized from JSON during deserialization, where the concrete
+/// author type is not known. It serializes back to a JSON object and exposes its fields for deep
+/// merging.
+#[derive(Clone, Default, Serialize)]
+pub struct StructMap(pub HashMap<String, EvaluationContextFieldValue>);
+
+impl EvaluationContextFieldValue {
+    /// Return a reference to the contained structure if this is a
+    /// [`Struct`](EvaluationContextFieldValue::Struct).
+    pub fn as_struct(&self) -> Option<&Arc<dyn StructValue>> {
+        match self {
+            Self::Struct(value) => Some(value),
+            _ => None,
+        }
+    }
+}
+
+impl PartialEq for EvaluationContextFieldValue {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Bool(left), Self::Bool(right)) => left == right,
+            (Self::Int(left), Self::Int(right)) => left == right,
+            (Self::Float(left), Self::Float(right)) => left == right,
+            (Self::String(left), Self::String(right)) => left == right,
+            (Self::DateTime(left), Self::DateTime(right)) => left == right,
+            (Self::Struct(left), Self::Struct(right)) => Arc::ptr_eq(left, right),
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Debug for EvaluationContextFieldValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Bool(value) => f.debug_tuple("Bool").field(value).finish(),
+            Self::Int(value) => f.debug_tuple("Int").field(value).finish(),
+            Self::Float(value) => f.debug_tuple("Float").field(value).finish(),
+            Self::String(value) => f.debug_tuple("String").field(value).finish(),
+            Self::DateTime(value) => f.debug_tuple("DateTime").field(value).finish(),
+            Self::Struct(_) => f.debug_tuple("Struct").finish(),
+        }
+    }
+}
+
+impl Serialize for EvaluationContextFieldValue {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        match self {
+            Self::Bool(value) => serializer.serialize_bool(*value),
+            Self::Int(value) => serializer.serialize_i64(*value),
+            Self::Float(value) => serializer.serialize_f64(*value),
+            Self::String(value) => serializer.serialize_str(value),
+            Self::DateTime(value) => serializer.serialize_str(
+                &value
+                    .format(&Rfc3339)
+                    .map_err(serde::ser::Error::custom)?,
+            ),
+            Self::Struct(value) => value.as_ref().serialize(serializer),
+        }
+    }
+}
+
+impl EvaluationContextFieldValue {
+    /// Build a field value from an already parsed JSON value, coercing numbers to
+    /// [`Int`](EvaluationContextFieldValue::Int)/[`Float`](EvaluationContextFieldValue::Float),
+    /// attempting an RFC3339 parse on strings (falling back to
+    /// [`String`](EvaluationContextFieldValue::String)), and turning nested objects into a
+    /// map-backed [`Struct`](EvaluationContextFieldValue::Struct).
+    pub(crate) fn from_json(value: serde_json::Value) -> Self {
+        match value {
+            serde_json::Value::Bool(value) => Self::Bool(value),
+            serde_json::Value::Number(number) => match number.as_i64() {
+                Some(value) => Self::Int(value),
+                None => Self::Float(number.as_f64().unwrap_or_default()),
+            },
+            serde_json::Value::String(value) => match OffsetDateTime::parse(&value, &Rfc3339) {
+                Ok(datetime) => Self::DateTime(datetime),
+                Err(_) => Self::String(value),
+            },
+            serde_json::Value::Object(object) => {
+                let fields = object
+                    .into_iter()
+                    .map(|(key, value)| (key, Self::from_json(value)))
+                    .collect();
+                Self::Struct(Arc::new(StructMap(fields)))
+            }
+            other => Self::String(other.to_string()),
+        }
+    }
+}
+
+impl From<bool> for EvaluationContextFieldValue {
+    fn from(value: bool) -> Self {
+        Self::Bool(value)
+    }
+}
+
+macro_rules! impl_from_int {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl From<$ty> for EvaluationContextFieldValue {
+                fn from(value: $ty) -> Self {
+                    Self::Int(i64::from(value))
+                }
+            }
+        )*
+    };
+}
+
+impl_from_int!(i8, i16, i32, i64, u8, u16, u32);
+
+impl From<f32> for EvaluationContextFieldValue {
+    fn from(value: f32) -> Self {
+        Self::Float(f64::from(value))
+    }
+}
+
+impl From<f64> for EvaluationContextFieldValue {
+    fn from(value: f64) -> Self {
+        Self::Float(value)
+    }
+}
+
+impl From<&str> for EvaluationContextFieldValue {
+    fn from(value: &str) -> Self {
+        Self::String(value.to_string())
+    }
+}
+
+impl From<String> for EvaluationContextFieldValue {
+    fn from(value: String) -> Self {
+        Self::String(value)
+    }
+}
+
+impl From<OffsetDateTime> for EvaluationContextFieldValue {
+    fn from(value: OffsetDateTime) -> Self {
+        Self::DateTime(value)
+    }
+}
+
+impl<'de> Deserialize<'de> for EvaluationContextFieldValue {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = serde_json::Value::deserialize(deserializer).map_err(de::Error::custom)?;
+        Ok(Self::from_json(value))
+    }
+}
@@ -0,0 +1,5 @@
+mod context;
+mod field_value;
+
+pub use context::*;
+pub use field_value::*;
@@ -0,0 +1,9 @@
+//! An OpenFeature SDK implementation in Rust.
+
+mod evaluation;
+mod provider;
+mod targeting;
+
+pub use evaluation::*;
+pub use provider::*;
+pub use targeting::*;
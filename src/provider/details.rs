@@ -1,10 +1,11 @@
+use serde::{Deserialize, Serialize};
 use typed_builder::TypedBuilder;
 
-use crate::{EvaluationReason, FlagMetadata};
+use crate::{EvaluationReason, FlagMetadata, FlagMetadataValue};
 
 /// A structure which contains a subset of the fields defined in the evaluation details,
 /// representing the result of the provider's flag resolution process.
-#[derive(Clone, TypedBuilder, Debug)]
+#[derive(Clone, TypedBuilder, Debug, Serialize, Deserialize)]
 pub struct ResolutionDetails<T> {
     /// In cases of normal execution, the provider MUST populate the resolution details structure's
     /// value field with the resolved flag value.
@@ -14,16 +15,19 @@ pub struct ResolutionDetails<T> {
     /// structure's variant field with a string identifier corresponding to the returned flag
     /// value.
     #[builder(default, setter(strip_option))]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub variant: Option<String>,
 
     /// The provider SHOULD populate the resolution details structure's reason field with "STATIC",
     /// "DEFAULT", "TARGETING_MATCH", "SPLIT", "CACHED", "DISABLED", "UNKNOWN", "ERROR" or some
     /// other string indicating the semantic reason for the returned flag value.
     #[builder(default, setter(strip_option))]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub reason: Option<EvaluationReason>,
 
     /// The provider SHOULD populate the resolution details structure's flag metadata field.
     #[builder(default, setter(strip_option))]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub flag_metadata: Option<FlagMetadata>,
 }
 
@@ -48,4 +52,37 @@ impl<T> ResolutionDetails<T> {
             flag_metadata: None,
         }
     }
+
+    /// Attach `key`/`value` to the flag metadata, creating the metadata map if necessary, and
+    /// return `self` for chaining. Mirrors [`EvaluationContext::with_custom_field`].
+    ///
+    /// [`EvaluationContext::with_custom_field`]: crate::EvaluationContext::with_custom_field
+    #[must_use]
+    pub fn with_metadata(
+        mut self,
+        key: impl Into<String>,
+        value: impl Into<FlagMetadataValue>,
+    ) -> Self {
+        self.flag_metadata
+            .get_or_insert_with(FlagMetadata::default)
+            .values
+            .insert(key.into(), value.into());
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn with_metadata_creates_and_chains() {
+        let details = ResolutionDetails::<bool>::new(true)
+            .with_metadata("ruleId", "r-1")
+            .with_metadata("ttl", 30i64);
+
+        let metadata = details.flag_metadata.expect("metadata should be created");
+        assert_eq!(metadata.get_string("ruleId"), Some("r-1"));
+        assert_eq!(metadata.get_int("ttl"), Some(30));
+    }
 }
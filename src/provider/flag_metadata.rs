@@ -0,0 +1,136 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+/// A structure which supports definition of arbitrary properties, with keys of type string, and
+/// values of type boolean, string, or number, supplied by the provider alongside a flag
+/// resolution.
+#[derive(Clone, PartialEq, Default, Debug, Serialize, Deserialize)]
+pub struct FlagMetadata {
+    /// The key/value pairs supplied by the provider.
+    #[serde(flatten)]
+    pub values: HashMap<String, FlagMetadataValue>,
+}
+
+impl FlagMetadata {
+    /// Return the boolean value stored under `key`, if any.
+    pub fn get_bool(&self, key: &str) -> Option<bool> {
+        match self.values.get(key)? {
+            FlagMetadataValue::Bool(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Return the integer value stored under `key`, if any.
+    pub fn get_int(&self, key: &str) -> Option<i64> {
+        match self.values.get(key)? {
+            FlagMetadataValue::Int(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    /// Return the floating point value stored under `key`, if any.
+    ///
+    /// An [`Int`](FlagMetadataValue::Int) entry is coerced to a float, matching the leniency of the
+    /// evaluation context getters.
+    pub fn get_float(&self, key: &str) -> Option<f64> {
+        match self.values.get(key)? {
+            FlagMetadataValue::Float(value) => Some(*value),
+            FlagMetadataValue::Int(value) => Some(*value as f64),
+            _ => None,
+        }
+    }
+
+    /// Return the string value stored under `key`, if any.
+    pub fn get_string(&self, key: &str) -> Option<&str> {
+        match self.values.get(key)? {
+            FlagMetadataValue::String(value) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Iterate over all metadata key/value pairs.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &FlagMetadataValue)> {
+        self.values.iter().map(|(key, value)| (key.as_str(), value))
+    }
+}
+
+/// A single metadata value, restricted to the types allowed by the specification.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum FlagMetadataValue {
+    /// A boolean value.
+    Bool(bool),
+
+    /// An integer value.
+    Int(i64),
+
+    /// A floating point value.
+    Float(f64),
+
+    /// A string value.
+    String(String),
+}
+
+impl From<bool> for FlagMetadataValue {
+    fn from(value: bool) -> Self {
+        Self::Bool(value)
+    }
+}
+
+impl From<i64> for FlagMetadataValue {
+    fn from(value: i64) -> Self {
+        Self::Int(value)
+    }
+}
+
+impl From<f64> for FlagMetadataValue {
+    fn from(value: f64) -> Self {
+        Self::Float(value)
+    }
+}
+
+impl From<String> for FlagMetadataValue {
+    fn from(value: String) -> Self {
+        Self::String(value)
+    }
+}
+
+impl From<&str> for FlagMetadataValue {
+    fn from(value: &str) -> Self {
+        Self::String(value.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metadata() -> FlagMetadata {
+        let mut values = HashMap::new();
+        values.insert("bool".to_string(), FlagMetadataValue::Bool(true));
+        values.insert("int".to_string(), FlagMetadataValue::Int(100));
+        values.insert("float".to_string(), FlagMetadataValue::Float(3.14));
+        values.insert("string".to_string(), FlagMetadataValue::String("Hello".to_string()));
+        FlagMetadata { values }
+    }
+
+    #[test]
+    fn typed_access() {
+        let metadata = metadata();
+
+        assert_eq!(metadata.get_bool("bool"), Some(true));
+        assert_eq!(metadata.get_int("int"), Some(100));
+        assert_eq!(metadata.get_float("float"), Some(3.14));
+        assert_eq!(metadata.get_string("string"), Some("Hello"));
+
+        // An integer entry is coerced when read as a float.
+        assert_eq!(metadata.get_float("int"), Some(100.0));
+
+        // Type mismatches and missing keys yield `None`.
+        assert_eq!(metadata.get_int("bool"), None);
+        assert_eq!(metadata.get_string("missing"), None);
+
+        assert_eq!(metadata.iter().count(), 4);
+    }
+}
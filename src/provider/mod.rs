@@ -0,0 +1,7 @@
+mod details;
+mod flag_metadata;
+mod reason;
+
+pub use details::*;
+pub use flag_metadata::*;
+pub use reason::*;
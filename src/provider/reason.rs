@@ -0,0 +1,83 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// The reason a flag evaluation produced a particular value.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum EvaluationReason {
+    /// The resolved value is static (no dynamic evaluation).
+    Static,
+
+    /// The resolved value fell back to a default (e.g. no targeting rule matched).
+    Default,
+
+    /// The resolved value was the result of a dynamic evaluation, such as a rule or targeting
+    /// match.
+    TargetingMatch,
+
+    /// The resolved value was the result of pseudorandom assignment.
+    Split,
+
+    /// The resolved value was retrieved from cache.
+    Cached,
+
+    /// The resolved value was the result of the flag being disabled in the management system.
+    Disabled,
+
+    /// The reason for the resolved value could not be determined.
+    Unknown,
+
+    /// The resolved value was the result of an error.
+    Error,
+
+    /// Any other provider-specific reason.
+    Other(String),
+}
+
+impl EvaluationReason {
+    /// The spec identifier for this reason, as carried on the wire.
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Static => "STATIC",
+            Self::Default => "DEFAULT",
+            Self::TargetingMatch => "TARGETING_MATCH",
+            Self::Split => "SPLIT",
+            Self::Cached => "CACHED",
+            Self::Disabled => "DISABLED",
+            Self::Unknown => "UNKNOWN",
+            Self::Error => "ERROR",
+            Self::Other(reason) => reason,
+        }
+    }
+}
+
+/// The reason is carried on the wire as a bare string using the specification's identifiers, so
+/// that it round-trips against remote backends and the reason strings documented on
+/// [`ResolutionDetails`](crate::ResolutionDetails). Unknown identifiers deserialize into
+/// [`Other`](EvaluationReason::Other).
+impl Serialize for EvaluationReason {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for EvaluationReason {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let reason = String::deserialize(deserializer)?;
+        Ok(match reason.as_str() {
+            "STATIC" => Self::Static,
+            "DEFAULT" => Self::Default,
+            "TARGETING_MATCH" => Self::TargetingMatch,
+            "SPLIT" => Self::Split,
+            "CACHED" => Self::Cached,
+            "DISABLED" => Self::Disabled,
+            "UNKNOWN" => Self::Unknown,
+            "ERROR" => Self::Error,
+            _ => Self::Other(reason),
+        })
+    }
+}
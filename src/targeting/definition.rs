@@ -0,0 +1,83 @@
+use std::collections::HashMap;
+
+/// A single pseudorandom bucket within a [`Outcome::Split`].
+#[derive(Clone, Debug)]
+pub struct Bucket {
+    /// The variant selected when the targeting key falls into this bucket.
+    pub variant: String,
+
+    /// The relative weight of this bucket. Buckets are sized proportionally to the sum of all
+    /// weights in the split.
+    pub weight: u32,
+}
+
+/// The outcome selected when a rule's predicate evaluates truthy.
+#[derive(Clone, Debug)]
+pub enum Outcome {
+    /// Always select the named variant.
+    Variant(String),
+
+    /// Deterministically assign the targeting key to one of the buckets.
+    Split(Vec<Bucket>),
+}
+
+/// A targeting rule: a koto predicate and the outcome to apply when it matches.
+#[derive(Clone, Debug)]
+pub struct TargetingRule {
+    /// A koto expression evaluated against the `context` value map. The rule matches when the
+    /// expression evaluates truthy, i.e. to any value other than `null` or `false`.
+    pub script: String,
+
+    /// The outcome applied when the predicate matches.
+    pub outcome: Outcome,
+}
+
+impl TargetingRule {
+    /// Create a rule that selects `variant` when `script` evaluates truthy.
+    pub fn new(script: impl Into<String>, variant: impl Into<String>) -> Self {
+        Self {
+            script: script.into(),
+            outcome: Outcome::Variant(variant.into()),
+        }
+    }
+
+    /// Create a rule that splits matching subjects across `buckets`.
+    pub fn split(script: impl Into<String>, buckets: Vec<Bucket>) -> Self {
+        Self {
+            script: script.into(),
+            outcome: Outcome::Split(buckets),
+        }
+    }
+}
+
+/// A locally evaluated flag: an ordered list of rules, the variant values, and a default variant
+/// used when no rule matches.
+#[derive(Clone, Debug)]
+pub struct FlagDefinition<T> {
+    /// Rules evaluated in declaration order; the first truthy predicate selects its outcome.
+    pub rules: Vec<TargetingRule>,
+
+    /// The variant returned when no rule matches.
+    pub default_variant: String,
+
+    /// The value associated with each variant.
+    pub variants: HashMap<String, T>,
+}
+
+impl<T> FlagDefinition<T> {
+    /// Create a flag definition with no rules, resolving to `default_variant`.
+    pub fn new(default_variant: impl Into<String>, variants: HashMap<String, T>) -> Self {
+        Self {
+            rules: Vec::new(),
+            default_variant: default_variant.into(),
+            variants,
+        }
+    }
+
+    /// Append a rule, returning `self` for chaining.
+    #[must_use]
+    pub fn with_rule(mut self, rule: TargetingRule) -> Self {
+        self.rules.push(rule);
+        self
+    }
+}
@@ -0,0 +1,12 @@
+//! In-process, rule-based flag evaluation.
+//!
+//! The [`LocalEvaluationProvider`] resolves flags against an
+//! [`EvaluationContext`](crate::EvaluationContext) without delegating to a remote service. Rules
+//! are authored as small predicates in the embedded [koto](https://koto.dev) scripting language,
+//! e.g. `context.email.ends_with("@acme.com")`, and evaluated in declaration order.
+
+mod definition;
+mod provider;
+
+pub use definition::*;
+pub use provider::*;
@@ -0,0 +1,359 @@
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap},
+    hash::{Hash, Hasher},
+    sync::Mutex,
+};
+
+use koto::{bytecode::Chunk, prelude::*, Koto, Ptr};
+use time::format_description::well_known::Rfc3339;
+
+use crate::{
+    Bucket, EvaluationContext, EvaluationContextFieldValue, EvaluationReason, FlagDefinition,
+    FlagMetadata, FlagMetadataValue, Outcome, ResolutionDetails, TargetingRule,
+};
+
+/// A provider that evaluates [`FlagDefinition`]s locally against an [`EvaluationContext`] using the
+/// embedded koto scripting language, rather than delegating to a remote flag-resolution service.
+///
+/// Compiled rule scripts are cached keyed by flag and rule so that repeated evaluations do not
+/// recompile.
+pub struct LocalEvaluationProvider<T> {
+    flags: HashMap<String, FlagDefinition<T>>,
+    compiled: Mutex<HashMap<String, Ptr<Chunk>>>,
+}
+
+impl<T> Default for LocalEvaluationProvider<T> {
+    fn default() -> Self {
+        Self {
+            flags: HashMap::new(),
+            compiled: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl<T> LocalEvaluationProvider<T>
+where
+    T: Clone + Default,
+{
+    /// Create a provider with no flags.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register `definition` under `flag_key`, returning `self` for chaining.
+    #[must_use]
+    pub fn with_flag(mut self, flag_key: impl Into<String>, definition: FlagDefinition<T>) -> Self {
+        self.flags.insert(flag_key.into(), definition);
+        self
+    }
+
+    /// Evaluate `flag_key` against `context`, returning the resolved value along with the reason
+    /// the value was selected.
+    ///
+    /// Rules are evaluated in declaration order; the first predicate that evaluates truthy selects
+    /// its outcome with `reason = TARGETING_MATCH` (or `SPLIT` for fractional outcomes). When no
+    /// rule matches, the default variant is returned with `reason = DEFAULT`. Script errors surface
+    /// as `reason = ERROR` with the error recorded in the flag metadata.
+    pub fn evaluate(&self, flag_key: &str, context: &EvaluationContext) -> ResolutionDetails<T> {
+        let Some(flag) = self.flags.get(flag_key) else {
+            return self.errored(T::default(), &format!("flag `{flag_key}` is not defined"));
+        };
+
+        let context_map = build_context_map(context);
+
+        for (index, rule) in flag.rules.iter().enumerate() {
+            match self.run_rule(flag_key, index, &rule.script, &context_map) {
+                Ok(true) => return self.select(flag, &rule.outcome, context),
+                Ok(false) => continue,
+                Err(error) => return self.errored(self.variant_value(flag, &flag.default_variant), &error),
+            }
+        }
+
+        self.resolved(flag, &flag.default_variant, EvaluationReason::Default)
+    }
+
+    fn select(
+        &self,
+        flag: &FlagDefinition<T>,
+        outcome: &Outcome,
+        context: &EvaluationContext,
+    ) -> ResolutionDetails<T> {
+        match outcome {
+            Outcome::Variant(variant) => {
+                self.resolved(flag, variant, EvaluationReason::TargetingMatch)
+            }
+            Outcome::Split(buckets) => {
+                let variant = split_variant(buckets, context);
+                self.resolved(flag, variant, EvaluationReason::Split)
+            }
+        }
+    }
+
+    fn resolved(
+        &self,
+        flag: &FlagDefinition<T>,
+        variant: &str,
+        reason: EvaluationReason,
+    ) -> ResolutionDetails<T> {
+        ResolutionDetails {
+            value: self.variant_value(flag, variant),
+            variant: Some(variant.to_string()),
+            reason: Some(reason),
+            flag_metadata: None,
+        }
+    }
+
+    fn variant_value(&self, flag: &FlagDefinition<T>, variant: &str) -> T {
+        flag.variants.get(variant).cloned().unwrap_or_default()
+    }
+
+    fn errored(&self, value: T, message: &str) -> ResolutionDetails<T> {
+        ResolutionDetails {
+            value,
+            variant: None,
+            reason: Some(EvaluationReason::Error),
+            flag_metadata: Some(error_metadata(message)),
+        }
+    }
+
+    fn run_rule(
+        &self,
+        flag_key: &str,
+        index: usize,
+        script: &str,
+        context_map: &KMap,
+    ) -> Result<bool, String> {
+        let chunk = self.compile(flag_key, index, script)?;
+
+        let mut koto = Koto::default();
+        koto.prelude()
+            .insert("context", KValue::Map(context_map.clone()));
+
+        // A rule matches when its expression is truthy: following koto, `null` and `false` are
+        // falsy and any other value is a match.
+        match koto.run_chunk(chunk) {
+            Ok(KValue::Bool(matched)) => Ok(matched),
+            Ok(KValue::Null) => Ok(false),
+            Ok(_) => Ok(true),
+            Err(error) => Err(error.to_string()),
+        }
+    }
+
+    fn compile(&self, flag_key: &str, index: usize, script: &str) -> Result<Ptr<Chunk>, String> {
+        let cache_key = format!("{flag_key}#{index}");
+        let mut cache = self.compiled.lock().expect("compiled script cache poisoned");
+        if let Some(chunk) = cache.get(&cache_key) {
+            return Ok(chunk.clone());
+        }
+
+        let chunk = Koto::default().compile(script).map_err(|e| e.to_string())?;
+        cache.insert(cache_key, chunk.clone());
+        Ok(chunk)
+    }
+}
+
+fn build_context_map(context: &EvaluationContext) -> KMap {
+    let map = KMap::default();
+    if let Some(targeting_key) = &context.targeting_key {
+        map.insert("targeting_key", KValue::Str(targeting_key.as_str().into()));
+    }
+    for (key, value) in &context.custom_fields {
+        map.insert(key.as_str(), field_to_koto(value));
+    }
+    map
+}
+
+fn field_to_koto(value: &EvaluationContextFieldValue) -> KValue {
+    match value {
+        EvaluationContextFieldValue::Bool(value) => KValue::Bool(*value),
+        EvaluationContextFieldValue::Int(value) => KValue::Number((*value).into()),
+        EvaluationContextFieldValue::Float(value) => KValue::Number((*value).into()),
+        EvaluationContextFieldValue::String(value) => KValue::Str(value.as_str().into()),
+        EvaluationContextFieldValue::DateTime(value) => {
+            KValue::Str(value.format(&Rfc3339).unwrap_or_default().as_str().into())
+        }
+        EvaluationContextFieldValue::Struct(value) => match serde_json::to_value(value.as_ref()) {
+            Ok(serde_json::Value::Object(object)) => json_to_koto(serde_json::Value::Object(object)),
+            _ => KValue::Null,
+        },
+    }
+}
+
+fn json_to_koto(value: serde_json::Value) -> KValue {
+    match value {
+        serde_json::Value::Null => KValue::Null,
+        serde_json::Value::Bool(value) => KValue::Bool(value),
+        serde_json::Value::Number(number) => match number.as_i64() {
+            Some(value) => KValue::Number(value.into()),
+            None => KValue::Number(number.as_f64().unwrap_or_default().into()),
+        },
+        serde_json::Value::String(value) => KValue::Str(value.as_str().into()),
+        serde_json::Value::Array(values) => {
+            KValue::List(KList::from_slice(&values.into_iter().map(json_to_koto).collect::<Vec<_>>()))
+        }
+        serde_json::Value::Object(object) => {
+            let map = KMap::default();
+            for (key, value) in object {
+                map.insert(key.as_str(), json_to_koto(value));
+            }
+            KValue::Map(map)
+        }
+    }
+}
+
+fn split_variant<'a>(buckets: &'a [Bucket], context: &EvaluationContext) -> &'a str {
+    let total: u32 = buckets.iter().map(|bucket| bucket.weight).sum();
+    if total == 0 {
+        return buckets.first().map(|bucket| bucket.variant.as_str()).unwrap_or_default();
+    }
+
+    let mut hasher = DefaultHasher::new();
+    context
+        .targeting_key
+        .as_deref()
+        .unwrap_or_default()
+        .hash(&mut hasher);
+    let point = (hasher.finish() % u64::from(total)) as u32;
+
+    let mut cumulative = 0;
+    for bucket in buckets {
+        cumulative += bucket.weight;
+        if point < cumulative {
+            return &bucket.variant;
+        }
+    }
+
+    buckets
+        .last()
+        .map(|bucket| bucket.variant.as_str())
+        .unwrap_or_default()
+}
+
+fn error_metadata(message: &str) -> FlagMetadata {
+    let mut values = HashMap::new();
+    values.insert(
+        "error".to_string(),
+        FlagMetadataValue::String(message.to_string()),
+    );
+    FlagMetadata { values }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn variants() -> HashMap<String, String> {
+        [("on", "ON"), ("off", "OFF")]
+            .into_iter()
+            .map(|(variant, value)| (variant.to_string(), value.to_string()))
+            .collect()
+    }
+
+    fn context(email: &str) -> EvaluationContext {
+        EvaluationContext::builder()
+            .targeting_key("user-1")
+            .build()
+            .with_custom_field("email", email)
+    }
+
+    #[test]
+    fn matching_rule_selects_variant() {
+        let provider = LocalEvaluationProvider::new().with_flag(
+            "flag",
+            FlagDefinition::new("off", variants()).with_rule(TargetingRule::new(
+                r#"context.email.ends_with("@acme.com")"#,
+                "on",
+            )),
+        );
+
+        let details = provider.evaluate("flag", &context("bob@acme.com"));
+
+        assert_eq!(details.value, "ON");
+        assert_eq!(details.variant, Some("on".to_string()));
+        assert_eq!(details.reason, Some(EvaluationReason::TargetingMatch));
+    }
+
+    #[test]
+    fn non_matching_rule_falls_through_to_default() {
+        let provider = LocalEvaluationProvider::new().with_flag(
+            "flag",
+            FlagDefinition::new("off", variants())
+                .with_rule(TargetingRule::new(r#"context.email == "nobody""#, "on")),
+        );
+
+        let details = provider.evaluate("flag", &context("bob@acme.com"));
+
+        assert_eq!(details.value, "OFF");
+        assert_eq!(details.variant, Some("off".to_string()));
+        assert_eq!(details.reason, Some(EvaluationReason::Default));
+    }
+
+    #[test]
+    fn rules_are_evaluated_in_declaration_order() {
+        let provider = LocalEvaluationProvider::new().with_flag(
+            "flag",
+            FlagDefinition::new("off", variants())
+                .with_rule(TargetingRule::new("false", "off"))
+                .with_rule(TargetingRule::new("true", "on"))
+                .with_rule(TargetingRule::new("true", "off")),
+        );
+
+        let details = provider.evaluate("flag", &context("bob@acme.com"));
+
+        assert_eq!(details.variant, Some("on".to_string()));
+        assert_eq!(details.reason, Some(EvaluationReason::TargetingMatch));
+    }
+
+    #[test]
+    fn split_assigns_deterministic_bucket() {
+        let buckets = vec![
+            Bucket {
+                variant: "on".to_string(),
+                weight: 50,
+            },
+            Bucket {
+                variant: "off".to_string(),
+                weight: 50,
+            },
+        ];
+        let provider = LocalEvaluationProvider::new().with_flag(
+            "flag",
+            FlagDefinition::new("off", variants())
+                .with_rule(TargetingRule::split("true", buckets)),
+        );
+
+        let first = provider.evaluate("flag", &context("bob@acme.com"));
+        let second = provider.evaluate("flag", &context("bob@acme.com"));
+
+        assert_eq!(first.reason, Some(EvaluationReason::Split));
+        assert!(matches!(first.variant.as_deref(), Some("on") | Some("off")));
+        // The assignment is stable for a given targeting key, and compiled scripts are cached.
+        assert_eq!(first.variant, second.variant);
+    }
+
+    #[test]
+    fn script_error_surfaces_as_error_reason() {
+        let provider = LocalEvaluationProvider::new().with_flag(
+            "flag",
+            FlagDefinition::new("off", variants())
+                .with_rule(TargetingRule::new("this is not valid (((", "on")),
+        );
+
+        let details = provider.evaluate("flag", &context("bob@acme.com"));
+
+        assert_eq!(details.reason, Some(EvaluationReason::Error));
+        assert!(details
+            .flag_metadata
+            .and_then(|metadata| metadata.get_string("error").map(ToString::to_string))
+            .is_some());
+    }
+
+    #[test]
+    fn unknown_flag_is_an_error() {
+        let provider: LocalEvaluationProvider<String> = LocalEvaluationProvider::new();
+
+        let details = provider.evaluate("missing", &context("bob@acme.com"));
+
+        assert_eq!(details.reason, Some(EvaluationReason::Error));
+    }
+}